@@ -0,0 +1,79 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::graph::{Vertex, EvolutionOperator, InverseEvolutionOperator};
+
+/// A bounded join-semilattice: a value domain with a least element and a least-upper-bound
+/// operation. This is the value domain of the monotone dataflow framework below -- for
+/// example "is a target reachable" (booleans ordered by implication) or a set of
+/// parametrisation colors ordered by inclusion.
+pub trait Lattice : Clone + Eq {
+    /// The least element of the lattice.
+    fn bottom() -> Self;
+
+    /// Returns the least upper bound of `self` and `other`.
+    fn join(&self, other: &Self) -> Self;
+
+    /// Returns true if `self` is less than or equal to `other` in the lattice order.
+    fn less_or_equal(&self, other: &Self) -> bool {
+        &self.join(other) == other
+    }
+}
+
+/// Maps a lattice value flowing in along an edge, together with the vertex it flows
+/// into, to the contribution that edge makes to the target vertex's value.
+pub trait TransferFunction<V, L> where V: Vertex, L: Lattice {
+    fn apply(&self, incoming: &L, vertex: &V) -> L;
+}
+
+/// Computes the least fixed point of a monotone dataflow problem over `graph` by
+/// worklist iteration: every vertex in `vertices` starts at `bottom` (or the value given
+/// for it in `seed`, if any), and is pushed onto the worklist. Popping a vertex `v`
+/// recomputes its value as the join of `transfer.apply(pred_value, v)` over all of
+/// `v`'s predecessors (`InverseEvolutionOperator::prev_step`), joined with `v`'s current
+/// stored value (so a seed or an earlier contribution is never lost); if this is not
+/// equal to the stored value, the new value is stored and every successor of `v`
+/// (`EvolutionOperator::next_step`) is pushed back onto the worklist. Values only
+/// increase, so for a finite lattice this always terminates at the least fixed point.
+pub fn monotone_fixpoint<G, V, L, T>(
+    graph: &G,
+    vertices: impl IntoIterator<Item=V>,
+    seed: Option<&HashMap<V, L>>,
+    transfer: &T,
+) -> HashMap<V, L>
+    where G: EvolutionOperator<V> + InverseEvolutionOperator<V>, V: Vertex, L: Lattice, T: TransferFunction<V, L>
+{
+    let mut values: HashMap<V, L> = HashMap::new();
+    let mut worklist: VecDeque<V> = VecDeque::new();
+    let mut queued: HashSet<V> = HashSet::new();
+
+    for vertex in vertices {
+        let value = seed.and_then(|s| s.get(&vertex)).cloned().unwrap_or_else(L::bottom);
+        values.insert(vertex.clone(), value);
+        if queued.insert(vertex.clone()) {
+            worklist.push_back(vertex);
+        }
+    }
+
+    while let Some(vertex) = worklist.pop_front() {
+        queued.remove(&vertex);
+
+        let mut new_value = L::bottom();
+        for predecessor in graph.prev_step(&vertex) {
+            if let Some(predecessor_value) = values.get(&predecessor) {
+                new_value = new_value.join(&transfer.apply(predecessor_value, &vertex));
+            }
+        }
+
+        let old_value = values.entry(vertex.clone()).or_insert_with(L::bottom);
+        let joined_value = old_value.join(&new_value);
+        if !joined_value.less_or_equal(old_value) {
+            *old_value = joined_value;
+            for successor in graph.next_step(&vertex) {
+                if queued.insert(successor.clone()) {
+                    worklist.push_back(successor);
+                }
+            }
+        }
+    }
+
+    return values;
+}
@@ -1,11 +1,14 @@
+use std::collections::{HashMap, VecDeque};
 use std::hash::Hash;
-use crate::set::Set;
+use crate::set::{Set, SetAlgebra, IterableSet};
 
 /// Vertex is a marker trait for a struct that can be used as a graph vertex. Currently,
 /// such struct only needs to be cloneable and hashable. In the future, maybe extra
 /// restrictions can be added...
 pub trait Vertex : Eq + Clone + Hash {}
 
+impl<V: Eq + Clone + Hash> Vertex for V {}
+
 /// Evolution operator trait represents part of the di-graph structure -- specifically
 /// the forward edges of the graph. The reason evolution operator exists is the fact
 /// that not all di-graphs have to be finite or have a known state space. Using
@@ -26,4 +29,240 @@ pub trait InverseEvolutionOperator<V> where V: Vertex {
     fn prev_step(&self, source: &V) -> Self::PredecessorIterator;
 }
 
-pub trait VertexSet<V> : Set<V> where V: Vertex {}
\ No newline at end of file
+/// A [`Set`] of graph vertices that also supports the algebraic operations
+/// ([`SetAlgebra`]) and enumeration ([`IterableSet`]) that generic graph algorithms
+/// (reachability, SCC decomposition, ...) need. Any set representation that implements
+/// these three traits can be used as the vertex set of [`GraphAlgorithms`].
+pub trait VertexSet<V> : Set<V> + SetAlgebra<V> + IterableSet<V> where V: Vertex {}
+
+impl<V, S> VertexSet<V> for S where V: Vertex, S: Set<V> + SetAlgebra<V> + IterableSet<V> {}
+
+/// A graph whose vertex set is enumerable, e.g. [`crate::SimpleGraph`]. Needed by
+/// algorithms such as [`GraphAlgorithms::topological_order`] that must process every
+/// vertex rather than just those reachable from a single source.
+pub trait FiniteGraph<V> where V: Vertex {
+    type VertexIterator : Iterator<Item=V>;
+
+    /// Returns an iterator over every vertex of the graph.
+    fn vertices(&self) -> Self::VertexIterator;
+}
+
+/// Returned by [`GraphAlgorithms::topological_order`] and
+/// [`GraphAlgorithms::reverse_topological_order`] when the graph contains a cycle, which
+/// has no topological order.
+#[derive(Debug, Eq, PartialEq)]
+pub struct NotAcyclic;
+
+/// Collects the graph algorithms that can be implemented purely in terms of
+/// [`EvolutionOperator`] (and, where needed, [`InverseEvolutionOperator`] and
+/// [`FiniteGraph`]). The trait is
+/// generic in the graph `G`, the vertex type `V`, and the vertex set representation
+/// `Self::Set`, so the same algorithms work for both explicit and on-the-fly/symbolic
+/// graphs.
+pub trait GraphAlgorithms<G, V> where V: Vertex, G: EvolutionOperator<V> {
+    type Set : VertexSet<V>;
+
+    fn new_vertex_set(graph: &G) -> Self::Set;
+
+    fn reachable_states(graph: &G, source: &V) -> Self::Set {
+        let mut stack: Vec<G::SuccessorIterator> = Vec::new();
+        let mut result = Self::new_vertex_set(graph);
+        stack.push(graph.next_step(source));
+        result.insert(source.clone());
+        while let Some(it) = stack.last_mut() {
+            if let Some(t) = it.next() {
+                let visited = result.contains(&t);
+                if !visited {
+                    stack.push(graph.next_step(&t));
+                    result.insert(t);
+                }
+            } else {
+                stack.pop();
+            }
+        }
+        return result;
+    }
+
+    /// Dual of [`reachable_states`](GraphAlgorithms::reachable_states): computes the set
+    /// of vertices from which `source` is reachable, by walking
+    /// [`InverseEvolutionOperator::prev_step`] instead of `next_step`.
+    fn backward_reachable_states(graph: &G, source: &V) -> Self::Set
+        where G: InverseEvolutionOperator<V>
+    {
+        let mut stack: Vec<G::PredecessorIterator> = Vec::new();
+        let mut result = Self::new_vertex_set(graph);
+        stack.push(graph.prev_step(source));
+        result.insert(source.clone());
+        while let Some(it) = stack.last_mut() {
+            if let Some(t) = it.next() {
+                let visited = result.contains(&t);
+                if !visited {
+                    stack.push(graph.prev_step(&t));
+                    result.insert(t);
+                }
+            } else {
+                stack.pop();
+            }
+        }
+        return result;
+    }
+
+    /// Returns the *relative heads* of `set`: the members of `set` that have no
+    /// successor inside `set` other than (optionally) themselves. In a transition
+    /// system, these are the sink states of the sub-behavior described by `set` -- a
+    /// head with a self-loop and no other outgoing edge is a fixed point, so self-edges
+    /// are ignored when deciding whether a vertex has an outgoing edge within `set`.
+    fn relative_heads(graph: &G, set: &Self::Set) -> Self::Set {
+        let mut result = Self::new_vertex_set(graph);
+        for v in set.iter() {
+            if !graph.next_step(&v).any(|successor| successor != v && set.contains(&successor)) {
+                result.insert(v);
+            }
+        }
+        return result;
+    }
+
+    /// Returns the *relative roots* of `set`: the members of `set` that have no
+    /// predecessor inside `set` other than (optionally) themselves. In a transition
+    /// system, these are the source states of the sub-behavior described by `set`;
+    /// self-edges are ignored for the same reason as in
+    /// [`relative_heads`](GraphAlgorithms::relative_heads).
+    fn relative_roots(graph: &G, set: &Self::Set) -> Self::Set
+        where G: InverseEvolutionOperator<V>
+    {
+        let mut result = Self::new_vertex_set(graph);
+        for v in set.iter() {
+            if !graph.prev_step(&v).any(|predecessor| predecessor != v && set.contains(&predecessor)) {
+                result.insert(v);
+            }
+        }
+        return result;
+    }
+
+    /// Decomposes a (sub)graph into its strongly connected components, using the
+    /// forward-backward (Xie-Beerel) algorithm: the worklist starts with `universe`;
+    /// for each subset `S` popped from it, a pivot `p` is picked and `SCC(p)` is the
+    /// intersection of the states reachable from `p` and the states that reach `p`,
+    /// both staying inside `S`. The three remainders `F \ SCC`, `B \ SCC` and
+    /// `S \ (F ∪ B)` partition `S \ SCC` and are pushed back onto the worklist.
+    /// Terminates because every step removes at least one non-empty SCC.
+    fn scc_decomposition(graph: &G, universe: &Self::Set) -> Vec<Self::Set>
+        where G: InverseEvolutionOperator<V>
+    {
+        let mut worklist = vec![universe.union(universe)];
+        let mut components = Vec::new();
+        while let Some(s) = worklist.pop() {
+            if s.is_empty() {
+                continue;
+            }
+            let pivot = s.iter().next().unwrap();
+            let forward = restricted_reachable(&pivot, &s, || Self::new_vertex_set(graph), |v| graph.next_step(v));
+            let backward = restricted_reachable(&pivot, &s, || Self::new_vertex_set(graph), |v| graph.prev_step(v));
+            let scc = forward.intersect(&backward);
+            worklist.push(forward.minus(&scc));
+            worklist.push(backward.minus(&scc));
+            worklist.push(s.minus(&forward).minus(&backward));
+            components.push(scc);
+        }
+        return components;
+    }
+
+    /// Returns the vertices of `graph` in topological order: every vertex is emitted only
+    /// after all of its predecessors (Kahn's algorithm, driven by in-degrees computed
+    /// from [`InverseEvolutionOperator::prev_step`]). Fails with [`NotAcyclic`] if the
+    /// graph contains a cycle, since a cyclic graph has no topological order.
+    fn topological_order(graph: &G) -> Result<Vec<V>, NotAcyclic>
+        where G: FiniteGraph<V> + InverseEvolutionOperator<V>
+    {
+        let mut in_degree: HashMap<V, usize> = HashMap::new();
+        for vertex in graph.vertices() {
+            let degree = graph.prev_step(&vertex).count();
+            in_degree.insert(vertex, degree);
+        }
+
+        let mut worklist: VecDeque<V> = in_degree.iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(vertex, _)| vertex.clone())
+            .collect();
+
+        let mut order = Vec::new();
+        while let Some(vertex) = worklist.pop_front() {
+            order.push(vertex.clone());
+            for successor in graph.next_step(&vertex) {
+                if let Some(degree) = in_degree.get_mut(&successor) {
+                    // Only decrement (and push) while `degree` is still positive: if
+                    // `next_step`/`prev_step` disagree on the edge multiset (e.g. a
+                    // parallel edge counted differently on each side), `degree` can
+                    // already be zero here. Guarding avoids both an underflow panic and
+                    // pushing `successor` onto the worklist more than once.
+                    if *degree > 0 {
+                        *degree -= 1;
+                        if *degree == 0 {
+                            worklist.push_back(successor);
+                        }
+                    }
+                }
+            }
+        }
+
+        return if order.len() == in_degree.len() { Ok(order) } else { Err(NotAcyclic) };
+    }
+
+    /// The reverse of [`topological_order`](GraphAlgorithms::topological_order): every
+    /// vertex is emitted only after all of its successors, i.e. bottom-up with edges
+    /// flipped. Fails with [`NotAcyclic`] under the same condition.
+    fn reverse_topological_order(graph: &G) -> Result<Vec<V>, NotAcyclic>
+        where G: FiniteGraph<V> + InverseEvolutionOperator<V>
+    {
+        let mut order = Self::topological_order(graph)?;
+        order.reverse();
+        return Ok(order);
+    }
+
+}
+
+/// Explores the graph from `source` using `step` (either `next_step` or `prev_step`),
+/// but only ever follows edges into vertices that belong to `universe`. Shared by the
+/// forward and backward half of [`GraphAlgorithms::scc_decomposition`].
+fn restricted_reachable<V, S, I, New, Step>(source: &V, universe: &S, mut new_empty: New, mut step: Step) -> S
+    where V: Vertex, S: VertexSet<V>, I: Iterator<Item=V>, New: FnMut() -> S, Step: FnMut(&V) -> I
+{
+    let mut stack: Vec<I> = Vec::new();
+    let mut result = new_empty();
+    stack.push(step(source));
+    result.insert(source.clone());
+    while let Some(it) = stack.last_mut() {
+        if let Some(t) = it.next() {
+            if universe.contains(&t) && !result.contains(&t) {
+                stack.push(step(&t));
+                result.insert(t);
+            }
+        } else {
+            stack.pop();
+        }
+    }
+    return result;
+}
+
+/// Wraps a graph so that its forward edges become backward edges and vice versa, at
+/// zero runtime cost (borrowed from the `Reversed`/`AsUndirected` adapters in petgraph).
+/// This lets any algorithm written against [`EvolutionOperator`] traverse the graph
+/// backward -- or any algorithm written against [`InverseEvolutionOperator`] traverse it
+/// forward -- simply by wrapping it, instead of duplicating every traversal.
+pub struct Reversed<G>(pub G);
+
+impl<G, V> EvolutionOperator<V> for Reversed<G> where V: Vertex, G: InverseEvolutionOperator<V> {
+    type SuccessorIterator = G::PredecessorIterator;
+
+    fn next_step(&self, source: &V) -> Self::SuccessorIterator {
+        return self.0.prev_step(source);
+    }
+}
+
+impl<G, V> InverseEvolutionOperator<V> for Reversed<G> where V: Vertex, G: EvolutionOperator<V> {
+    type PredecessorIterator = G::SuccessorIterator;
+
+    fn prev_step(&self, source: &V) -> Self::PredecessorIterator {
+        return self.0.next_step(source);
+    }
+}
@@ -23,18 +23,25 @@ We try to adhere to the graph terminology as closely as possible, using vertex a
 
 */
 
+mod analysis;
 mod graph;
 mod set;
 
 use std::collections::{HashSet, HashMap};
-use std::hash::Hash;
 use std::vec::IntoIter;
 
-pub struct HashVertexSet<V: Hash + Eq> {
+pub use crate::analysis::{Lattice, TransferFunction, monotone_fixpoint};
+pub use crate::graph::{
+    Vertex, EvolutionOperator, InverseEvolutionOperator, VertexSet, FiniteGraph, NotAcyclic,
+    GraphAlgorithms, Reversed,
+};
+pub use crate::set::{Set, SetAlgebra, IterableSet};
+
+pub struct HashVertexSet<V: Vertex> {
     set: HashSet<V>
 }
 
-impl <V: Hash + Eq> VertexSet<V> for HashVertexSet<V> {
+impl <V: Vertex> Set<V> for HashVertexSet<V> {
 
     fn contains(&self, vertex: &V) -> bool {
         return self.set.contains(vertex);
@@ -49,6 +56,33 @@ impl <V: Hash + Eq> VertexSet<V> for HashVertexSet<V> {
     }
 }
 
+impl <V: Vertex> SetAlgebra<V> for HashVertexSet<V> {
+
+    fn union(&self, other: &Self) -> Self {
+        HashVertexSet { set: self.set.union(&other.set).cloned().collect() }
+    }
+
+    fn intersect(&self, other: &Self) -> Self {
+        HashVertexSet { set: self.set.intersection(&other.set).cloned().collect() }
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        HashVertexSet { set: self.set.difference(&other.set).cloned().collect() }
+    }
+}
+
+impl <V: Vertex> IterableSet<V> for HashVertexSet<V> {
+    type Iter = IntoIter<V>;
+
+    fn cardinality(&self) -> usize {
+        return self.set.len();
+    }
+
+    fn iter(&self) -> Self::Iter {
+        self.set.iter().cloned().collect::<Vec<V>>().into_iter()
+    }
+}
+
 pub struct SimpleGraph {
     vertices: HashSet<String>,
     successors: HashMap<String, Vec<String>>,
@@ -56,60 +90,37 @@ pub struct SimpleGraph {
 }
 
 impl EvolutionOperator<String> for SimpleGraph {
-    type Iterator = IntoIter<String>;
+    type SuccessorIterator = IntoIter<String>;
 
-    fn next(&self, source: &String) -> Self::Iterator {
+    fn next_step(&self, source: &String) -> Self::SuccessorIterator {
         return self.successors.get(source).unwrap().clone().into_iter()
     }
 }
 
-pub struct SimpleGraphAlgorithms;
+impl InverseEvolutionOperator<String> for SimpleGraph {
+    type PredecessorIterator = IntoIter<String>;
 
-impl GraphAlgorithms<SimpleGraph, String> for SimpleGraphAlgorithms {
-    type Set = HashVertexSet<String>;
-
-    fn new_vertex_set(graph: &SimpleGraph) -> Self::Set {
-        return HashVertexSet { set: HashSet::new() }
+    fn prev_step(&self, source: &String) -> Self::PredecessorIterator {
+        return self.predecessors.get(source).unwrap().clone().into_iter()
     }
 }
 
-pub trait VertexSet<V> {
-    fn contains(&self, vertex: &V) -> bool;
-    fn is_empty(&self) -> bool;
-    fn insert(&mut self, vertex: V) -> bool;
+impl FiniteGraph<String> for SimpleGraph {
+    type VertexIterator = IntoIter<String>;
+
+    fn vertices(&self) -> Self::VertexIterator {
+        self.vertices.iter().cloned().collect::<Vec<String>>().into_iter()
+    }
 }
 
-pub trait EvolutionOperator<V> {
-    type Iterator : Iterator<Item=V>;
+pub struct SimpleGraphAlgorithms;
 
-    fn next(&self, source: &V) -> Self::Iterator;
-    //fn next_ref(&self, source: &V) -> &Self::Iterator;
-}
+impl GraphAlgorithms<SimpleGraph, String> for SimpleGraphAlgorithms {
+    type Set = HashVertexSet<String>;
 
-pub trait GraphAlgorithms<G, V> where V: Clone, G: EvolutionOperator<V> {
-    type Set : VertexSet<V>;
-
-    fn new_vertex_set(graph: &G) -> Self::Set;
-
-    fn reachable_states(graph: &G, source: &V) -> Self::Set {
-        let mut stack: Vec<G::Iterator> = Vec::new();
-        let mut result = Self::new_vertex_set(graph);
-        stack.push(graph.next(source));
-        result.insert(source.clone());
-        while let Some(it) = stack.last_mut() {
-            if let Some(t) = it.next() {
-                let visited = result.contains(&t);
-                if !visited {
-                    stack.push(graph.next(&t));
-                    result.insert(t);
-                }
-            } else {
-                stack.pop();
-            }
-        }
-        return result;
+    fn new_vertex_set(_graph: &SimpleGraph) -> Self::Set {
+        return HashVertexSet { set: HashSet::new() }
     }
-
 }
 
 #[cfg(test)]
@@ -163,4 +174,243 @@ mod tests {
         assert!(reach_from_c.contains(&"C".to_string()));
 
     }
+
+    #[test]
+    fn topological_order_on_dag_and_cycle() {
+        // A -> B -> C is a DAG, so it has a unique topological (and reverse) order.
+        let mut vertices: HashSet<String> = HashSet::new();
+        for v in ["A", "B", "C"] {
+            vertices.insert(v.to_string());
+        }
+
+        let mut successors = HashMap::new();
+        successors.insert("A".to_string(), vec!["B".to_string()]);
+        successors.insert("B".to_string(), vec!["C".to_string()]);
+        successors.insert("C".to_string(), vec![]);
+
+        let mut predecessors = HashMap::new();
+        predecessors.insert("A".to_string(), vec![]);
+        predecessors.insert("B".to_string(), vec!["A".to_string()]);
+        predecessors.insert("C".to_string(), vec!["B".to_string()]);
+
+        let dag = SimpleGraph { vertices, successors, predecessors };
+
+        let order = SimpleGraphAlgorithms::topological_order(&dag).unwrap();
+        assert_eq!(order, vec!["A".to_string(), "B".to_string(), "C".to_string()]);
+
+        let reverse_order = SimpleGraphAlgorithms::reverse_topological_order(&dag).unwrap();
+        assert_eq!(reverse_order, vec!["C".to_string(), "B".to_string(), "A".to_string()]);
+
+        // A -> B -> C -> A is a cycle and has no topological order.
+        let mut vertices: HashSet<String> = HashSet::new();
+        for v in ["A", "B", "C"] {
+            vertices.insert(v.to_string());
+        }
+
+        let mut successors = HashMap::new();
+        successors.insert("A".to_string(), vec!["B".to_string()]);
+        successors.insert("B".to_string(), vec!["C".to_string()]);
+        successors.insert("C".to_string(), vec!["A".to_string()]);
+
+        let mut predecessors = HashMap::new();
+        predecessors.insert("A".to_string(), vec!["C".to_string()]);
+        predecessors.insert("B".to_string(), vec!["A".to_string()]);
+        predecessors.insert("C".to_string(), vec!["B".to_string()]);
+
+        let cycle = SimpleGraph { vertices, successors, predecessors };
+
+        assert_eq!(SimpleGraphAlgorithms::topological_order(&cycle), Err(NotAcyclic));
+        assert_eq!(SimpleGraphAlgorithms::reverse_topological_order(&cycle), Err(NotAcyclic));
+    }
+
+    #[test]
+    fn backward_reachable_states_and_reversed_adapter() {
+        // A -> B -> C.
+        let mut vertices: HashSet<String> = HashSet::new();
+        for v in ["A", "B", "C"] {
+            vertices.insert(v.to_string());
+        }
+
+        let mut successors = HashMap::new();
+        successors.insert("A".to_string(), vec!["B".to_string()]);
+        successors.insert("B".to_string(), vec!["C".to_string()]);
+        successors.insert("C".to_string(), vec![]);
+
+        let mut predecessors = HashMap::new();
+        predecessors.insert("A".to_string(), vec![]);
+        predecessors.insert("B".to_string(), vec!["A".to_string()]);
+        predecessors.insert("C".to_string(), vec!["B".to_string()]);
+
+        let graph = SimpleGraph { vertices: vertices.clone(), successors: successors.clone(), predecessors: predecessors.clone() };
+
+        let back_from_c = SimpleGraphAlgorithms::backward_reachable_states(&graph, &"C".to_string());
+        assert!(back_from_c.contains(&"A".to_string()));
+        assert!(back_from_c.contains(&"B".to_string()));
+        assert!(back_from_c.contains(&"C".to_string()));
+
+        let back_from_a = SimpleGraphAlgorithms::backward_reachable_states(&graph, &"A".to_string());
+        assert_eq!(back_from_a.cardinality(), 1);
+        assert!(back_from_a.contains(&"A".to_string()));
+
+        // Reversed flips next_step/prev_step without touching the underlying graph.
+        let original = SimpleGraph { vertices, successors, predecessors };
+        let reversed = crate::graph::Reversed(graph);
+        let reversed_next: HashSet<String> = reversed.next_step(&"C".to_string()).collect();
+        let original_prev: HashSet<String> = original.prev_step(&"C".to_string()).collect();
+        assert_eq!(reversed_next, original_prev);
+
+        let reversed_prev: HashSet<String> = reversed.prev_step(&"A".to_string()).collect();
+        let original_next: HashSet<String> = original.next_step(&"A".to_string()).collect();
+        assert_eq!(reversed_prev, original_next);
+    }
+
+    #[test]
+    fn scc_decomposition_finds_two_cycles() {
+        // Two disjoint cycles A <-> B and C <-> D, joined by a single one-way edge
+        // B -> C, so the graph decomposes into exactly the two SCCs {A, B} and {C, D}.
+        let mut vertices: HashSet<String> = HashSet::new();
+        for v in ["A", "B", "C", "D"] {
+            vertices.insert(v.to_string());
+        }
+
+        let mut successors = HashMap::new();
+        successors.insert("A".to_string(), vec!["B".to_string()]);
+        successors.insert("B".to_string(), vec!["A".to_string(), "C".to_string()]);
+        successors.insert("C".to_string(), vec!["D".to_string()]);
+        successors.insert("D".to_string(), vec!["C".to_string()]);
+
+        let mut predecessors = HashMap::new();
+        predecessors.insert("A".to_string(), vec!["B".to_string()]);
+        predecessors.insert("B".to_string(), vec!["A".to_string()]);
+        predecessors.insert("C".to_string(), vec!["B".to_string(), "D".to_string()]);
+        predecessors.insert("D".to_string(), vec!["C".to_string()]);
+
+        let graph = SimpleGraph { vertices, successors, predecessors };
+        let universe = SimpleGraphAlgorithms::reachable_states(&graph, &"A".to_string());
+
+        let mut sccs = SimpleGraphAlgorithms::scc_decomposition(&graph, &universe);
+        sccs.retain(|scc| !scc.is_empty());
+        let mut sizes: Vec<usize> = sccs.iter().map(|scc| scc.cardinality()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![2, 2]);
+
+        let ab = sccs.iter().find(|scc| scc.contains(&"A".to_string())).unwrap();
+        assert!(ab.contains(&"B".to_string()));
+        assert!(!ab.contains(&"C".to_string()));
+        assert!(!ab.contains(&"D".to_string()));
+
+        let cd = sccs.iter().find(|scc| scc.contains(&"C".to_string())).unwrap();
+        assert!(cd.contains(&"D".to_string()));
+    }
+
+    #[test]
+    fn hash_vertex_set_algebra() {
+        let mut a: HashVertexSet<String> = HashVertexSet { set: HashSet::new() };
+        a.insert("A".to_string());
+        a.insert("B".to_string());
+
+        let mut b: HashVertexSet<String> = HashVertexSet { set: HashSet::new() };
+        b.insert("B".to_string());
+        b.insert("C".to_string());
+
+        let union = a.union(&b);
+        assert!(union.contains(&"A".to_string()));
+        assert!(union.contains(&"B".to_string()));
+        assert!(union.contains(&"C".to_string()));
+        assert_eq!(union.cardinality(), 3);
+
+        let intersection = a.intersect(&b);
+        assert_eq!(intersection.cardinality(), 1);
+        assert!(intersection.contains(&"B".to_string()));
+
+        let difference = a.minus(&b);
+        assert_eq!(difference.cardinality(), 1);
+        assert!(difference.contains(&"A".to_string()));
+
+        let complement = a.complement_within(&union);
+        assert_eq!(complement.cardinality(), 1);
+        assert!(complement.contains(&"C".to_string()));
+    }
+
+    #[test]
+    fn relative_heads_finds_self_loop_fixed_point() {
+        // A -> B -> C -> C (C has a self-loop and no other outgoing edge), so C is both
+        // a relative head (fixed point) and, since B points into it from inside the set,
+        // not a relative root.
+        let mut vertices: HashSet<String> = HashSet::new();
+        vertices.insert("A".to_string());
+        vertices.insert("B".to_string());
+        vertices.insert("C".to_string());
+
+        let mut successors = HashMap::new();
+        successors.insert("A".to_string(), vec!["B".to_string()]);
+        successors.insert("B".to_string(), vec!["C".to_string()]);
+        successors.insert("C".to_string(), vec!["C".to_string()]);
+
+        let mut predecessors = HashMap::new();
+        predecessors.insert("A".to_string(), vec![]);
+        predecessors.insert("B".to_string(), vec!["A".to_string()]);
+        predecessors.insert("C".to_string(), vec!["B".to_string(), "C".to_string()]);
+
+        let graph = SimpleGraph { vertices, successors, predecessors };
+        let universe = SimpleGraphAlgorithms::reachable_states(&graph, &"A".to_string());
+
+        let heads = SimpleGraphAlgorithms::relative_heads(&graph, &universe);
+        assert!(heads.contains(&"C".to_string()));
+        assert_eq!(heads.cardinality(), 1);
+
+        let roots = SimpleGraphAlgorithms::relative_roots(&graph, &universe);
+        assert!(roots.contains(&"A".to_string()));
+        assert_eq!(roots.cardinality(), 1);
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    struct ColorSet(HashSet<&'static str>);
+
+    impl crate::analysis::Lattice for ColorSet {
+        fn bottom() -> Self {
+            ColorSet(HashSet::new())
+        }
+
+        fn join(&self, other: &Self) -> Self {
+            ColorSet(self.0.union(&other.0).cloned().collect())
+        }
+    }
+
+    struct Identity;
+
+    impl crate::analysis::TransferFunction<String, ColorSet> for Identity {
+        fn apply(&self, incoming: &ColorSet, _vertex: &String) -> ColorSet {
+            incoming.clone()
+        }
+    }
+
+    #[test]
+    fn monotone_fixpoint_keeps_seed_alongside_predecessor_contributions() {
+        // A -> B, with A seeded to {"red"} and B seeded to {"blue"}. Since B has a
+        // predecessor (A) contributing an incomparable value, the least fixed point at B
+        // must be the join {"red", "blue"}, not just whichever value is computed last.
+        let mut vertices: HashSet<String> = HashSet::new();
+        vertices.insert("A".to_string());
+        vertices.insert("B".to_string());
+
+        let mut successors = HashMap::new();
+        successors.insert("A".to_string(), vec!["B".to_string()]);
+        successors.insert("B".to_string(), vec![]);
+
+        let mut predecessors = HashMap::new();
+        predecessors.insert("A".to_string(), vec![]);
+        predecessors.insert("B".to_string(), vec!["A".to_string()]);
+
+        let graph = SimpleGraph { vertices: vertices.clone(), successors, predecessors };
+
+        let mut seed = HashMap::new();
+        seed.insert("A".to_string(), ColorSet(["red"].into_iter().collect()));
+        seed.insert("B".to_string(), ColorSet(["blue"].into_iter().collect()));
+
+        let result = crate::analysis::monotone_fixpoint(&graph, vertices, Some(&seed), &Identity);
+
+        assert_eq!(result.get("A").unwrap().0, ["red"].into_iter().collect());
+        assert_eq!(result.get("B").unwrap().0, ["red", "blue"].into_iter().collect());
+    }
 }
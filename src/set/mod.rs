@@ -14,4 +14,40 @@ pub trait Set<V> where V: Eq {
     /// false if not.
     fn insert(&mut self, item: V) -> bool;
 
-}
\ No newline at end of file
+}
+
+/// Extends [`Set`] with the algebraic operations needed by fixpoint graph algorithms
+/// (SCC decomposition, attractors, CTL model checking, ...). Every operation produces a new
+/// set instead of mutating `self`, so the trait works equally well for explicit (e.g.
+/// hash-based) and symbolic (e.g. BDD-backed) set representations.
+pub trait SetAlgebra<V> : Set<V> + Sized where V: Eq {
+
+    /// Returns a set containing every element of `self` or `other`.
+    fn union(&self, other: &Self) -> Self;
+
+    /// Returns a set containing every element present in both `self` and `other`.
+    fn intersect(&self, other: &Self) -> Self;
+
+    /// Returns a set containing every element of `self` that is not present in `other`.
+    fn minus(&self, other: &Self) -> Self;
+
+    /// Returns the complement of `self` relative to `universe`, i.e. the elements of
+    /// `universe` that are not in `self`. Assumes `self` is a subset of `universe`.
+    fn complement_within(&self, universe: &Self) -> Self {
+        universe.minus(self)
+    }
+
+}
+
+/// Extends [`Set`] for representations that can enumerate their own elements. Not every
+/// set representation can do this efficiently (a symbolic, BDD-backed set typically can't),
+/// so this is kept separate from [`SetAlgebra`] and only implemented where it makes sense.
+pub trait IterableSet<V> : Set<V> where V: Eq {
+    type Iter : Iterator<Item=V>;
+
+    /// Returns the number of elements in the set.
+    fn cardinality(&self) -> usize;
+
+    /// Returns an iterator over the elements of the set.
+    fn iter(&self) -> Self::Iter;
+}